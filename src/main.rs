@@ -1,21 +1,38 @@
+mod push;
+mod tui;
+
 use anyhow::{anyhow, Result};
 use chrono::{DateTime, Utc};
-use clap::Parser;
+use clap::{Parser, Subcommand, ValueEnum};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::fs::{File, OpenOptions};
-use std::io::{self, BufReader, BufWriter, Write};
+use std::io::{self, BufReader, BufWriter, IsTerminal, Write};
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
-use surge_ping::{Client, Config, PingIdentifier, PingSequence};
+use surge_ping::{Client, Config, PingIdentifier, PingSequence, ICMP};
+
+pub(crate) const BUCKET_ORDER: [&str; 12] = [
+    "0-50ms", "50-100ms", "100-150ms", "150-200ms", "200-250ms",
+    "250-300ms", "300-350ms", "350-400ms", "400-450ms", "450-500ms",
+    "500-999ms", ">1000ms",
+];
 
+// Number of most-recent samples the `--tui` dashboard keeps per target for its table.
+const RECENT_SAMPLES: usize = 20;
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
-    /// Target host or IP
-    target: Option<String>,
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Target host(s) or IP(s) to ping
+    targets: Vec<String>,
+
+    #[arg(long, help = "File with one target host per line, added to the positional targets")]
+    targets_file: Option<PathBuf>,
 
     #[arg(short, long, help = "Packets to send (default: continuous)")]
     count: Option<u64>,
@@ -26,6 +43,18 @@ struct Args {
     #[arg(short = 's', long, default_value_t = 56, help = "ICMP payload size")]
     packet_size: usize,
 
+    #[arg(long, value_enum, default_value_t = Mode::Icmp, help = "Ping mode: icmp or tcp")]
+    mode: Mode,
+
+    #[arg(long, default_value_t = 443, help = "Port to connect to in tcp mode (overridden by host:port)")]
+    port: u16,
+
+    #[arg(short = '4', long, help = "Prefer an IPv4 address when resolving", conflicts_with = "prefer_v6")]
+    prefer_v4: bool,
+
+    #[arg(short = '6', long, help = "Prefer an IPv6 address when resolving")]
+    prefer_v6: bool,
+
     #[arg(short, long, default_value = "ping_history.json", help = "Output file")]
     output: String,
 
@@ -37,24 +66,79 @@ struct Args {
 
     #[arg(long, help = "Interval in seconds to save results automatically")]
     save_interval: Option<u64>,
+
+    #[arg(long, help = "Render a live terminal dashboard instead of line-by-line output")]
+    tui: bool,
+
+    #[arg(long, help = "Push each interval's/session's stats to this http(s):// or ws(s):// endpoint")]
+    push_url: Option<String>,
+
+    #[arg(long, help = "Run headless as a supervised systemd service (sd-notify readiness/watchdog, SIGTERM handling)")]
+    daemon: bool,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Read back saved ping history and filter it by time range
+    Query(QueryArgs),
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum Mode {
+    Icmp,
+    Tcp,
+}
+
+#[derive(Parser, Debug)]
+struct QueryArgs {
+    /// History file to read (JSON or CSV, picked by extension)
+    input: PathBuf,
+
+    #[arg(long, help = "RFC3339 timestamp; only entries at or after this are kept")]
+    from: Option<DateTime<Utc>>,
+
+    #[arg(long, help = "RFC3339 timestamp; only entries at or before this are kept")]
+    to: Option<DateTime<Utc>>,
+
+    #[arg(short, long, help = "Re-export the filtered entries to this file")]
+    output: Option<PathBuf>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
-struct PingStats {
-    target: String,
-    timestamp: DateTime<Utc>,
-    sent: u64,
-    received: u64,
-    loss_percent: f64,
-    min: Option<f32>,
-    max: Option<f32>,
-    avg: Option<f32>,
-    latency_buckets: HashMap<String, u64>,
+pub(crate) struct PingStats {
+    pub(crate) target: String,
+    // Absent in history files written before IPv6 support; defaults to "v4"
+    // since that was the only family the tool could ever produce.
+    #[serde(default = "default_family")]
+    pub(crate) family: String,
+    pub(crate) timestamp: DateTime<Utc>,
+    pub(crate) sent: u64,
+    pub(crate) received: u64,
+    pub(crate) loss_percent: f64,
+    pub(crate) min: Option<f32>,
+    pub(crate) max: Option<f32>,
+    pub(crate) avg: Option<f32>,
+    // Absent in history files written before percentile/jitter support.
+    #[serde(default)]
+    pub(crate) p50: Option<f32>,
+    #[serde(default)]
+    pub(crate) p90: Option<f32>,
+    #[serde(default)]
+    pub(crate) p95: Option<f32>,
+    #[serde(default)]
+    pub(crate) p99: Option<f32>,
+    #[serde(default)]
+    pub(crate) jitter: Option<f32>,
+    pub(crate) latency_buckets: HashMap<String, u64>,
+}
+
+fn default_family() -> String {
+    "v4".to_string()
 }
 
 impl PingStats {
     // Creates a new, empty stats object for a session.
-    fn new(target: String) -> Self {
+    fn new(target: String, family: String) -> Self {
         let buckets = [
             "0-50ms", "50-100ms", "100-150ms", "150-200ms", "200-250ms",
             "250-300ms", "300-350ms", "350-400ms", "400-450ms", "450-500ms",
@@ -66,6 +150,7 @@ impl PingStats {
 
         Self {
             target,
+            family,
             timestamp: Utc::now(),
             sent: 0,
             received: 0,
@@ -73,6 +158,11 @@ impl PingStats {
             min: None,
             max: None,
             avg: None,
+            p50: None,
+            p90: None,
+            p95: None,
+            p99: None,
+            jitter: None,
             latency_buckets: buckets,
         }
     }
@@ -90,10 +180,37 @@ impl PingStats {
             self.min = None;
             self.max = None;
             self.avg = None;
+            self.p50 = None;
+            self.p90 = None;
+            self.p95 = None;
+            self.p99 = None;
+            self.jitter = None;
         } else {
             self.min = Some((times.iter().fold(f32::MAX, |a, &b| a.min(b)) * 100.0).round() / 100.0);
             self.max = Some((times.iter().fold(f32::MIN, |a, &b| a.max(b)) * 100.0).round() / 100.0);
             self.avg = Some((times.iter().sum::<f32>() / times.len() as f32 * 100.0).round() / 100.0);
+
+            // Nearest-rank percentiles, computed on a sorted copy so `times` stays in send order for jitter below.
+            let mut sorted = times.to_vec();
+            sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            let percentile = |p: f64| -> f32 {
+                let rank = ((p / 100.0 * sorted.len() as f64).ceil() as usize)
+                    .saturating_sub(1)
+                    .min(sorted.len() - 1);
+                sorted[rank]
+            };
+            self.p50 = Some((percentile(50.0) * 100.0).round() / 100.0);
+            self.p90 = Some((percentile(90.0) * 100.0).round() / 100.0);
+            self.p95 = Some((percentile(95.0) * 100.0).round() / 100.0);
+            self.p99 = Some((percentile(99.0) * 100.0).round() / 100.0);
+
+            // Mean absolute difference between consecutive samples, in send order.
+            self.jitter = if times.len() > 1 {
+                let diff_sum: f32 = times.windows(2).map(|w| (w[1] - w[0]).abs()).sum();
+                Some((diff_sum / (times.len() - 1) as f32 * 100.0).round() / 100.0)
+            } else {
+                None
+            };
         }
 
         // Recalculate latency distribution buckets
@@ -145,23 +262,21 @@ fn save_results_csv(stats: &PingStats, path: &Path) -> Result<()> {
     let file = OpenOptions::new().write(true).create(true).append(true).open(path)?;
     let mut writer = csv::Writer::from_writer(file);
 
-    // Write header if file is new
+    // Write header if file is new. New fields (`family`, then the percentiles
+    // and jitter) are appended at the end rather than inserted earlier so
+    // files written by older versions still line up column-for-column with
+    // rows written after.
     if !file_exists {
         writer.write_record(&[
-            "target", "timestamp", "sent", "received", "loss_percent", 
-            "min", "max", "avg", "0-50ms", "50-100ms", "100-150ms", 
-            "150-200ms", "200-250ms", "250-300ms", "300-350ms", 
-            "350-400ms", "400-450ms", "450-500ms", "500-999ms", ">1000ms"
+            "target", "timestamp", "sent", "received", "loss_percent",
+            "min", "max", "avg", "0-50ms", "50-100ms", "100-150ms",
+            "150-200ms", "200-250ms", "250-300ms", "300-350ms",
+            "350-400ms", "400-450ms", "450-500ms", "500-999ms", ">1000ms",
+            "family", "p50", "p90", "p95", "p99", "jitter",
         ])?;
     }
 
     // Write data row
-    let bucket_order = [
-        "0-50ms", "50-100ms", "100-150ms", "150-200ms", "200-250ms",
-        "250-300ms", "300-350ms", "350-400ms", "400-450ms", "450-500ms",
-        "500-999ms", ">1000ms"
-    ];
-    
     let mut record = vec![
         stats.target.clone(),
         stats.timestamp.to_rfc3339(),
@@ -172,11 +287,15 @@ fn save_results_csv(stats: &PingStats, path: &Path) -> Result<()> {
         stats.max.map_or("".to_string(), |v| format!("{:.2}", v)),
         stats.avg.map_or("".to_string(), |v| format!("{:.2}", v)),
     ];
-    
-    for bucket in &bucket_order {
+
+    for bucket in &BUCKET_ORDER {
         record.push(stats.latency_buckets.get(*bucket).unwrap_or(&0).to_string());
     }
-    
+    record.push(stats.family.clone());
+    for percentile in [stats.p50, stats.p90, stats.p95, stats.p99, stats.jitter] {
+        record.push(percentile.map_or("".to_string(), |v| format!("{:.2}", v)));
+    }
+
     writer.write_record(&record)?;
     writer.flush()?;
     Ok(())
@@ -191,6 +310,98 @@ fn save_results_generic(stats: &PingStats, path: &Path) -> Result<()> {
     }
 }
 
+// Reads back a saved history file, prints only the entries whose `timestamp`
+// falls within `[from, to]` (either bound optional), and re-exports them to
+// `--output` when one is given.
+fn run_query(args: QueryArgs) -> Result<()> {
+    let is_csv = args.input.extension().and_then(|s| s.to_str()) == Some("csv");
+    let matched = if is_csv {
+        query_csv(&args.input, args.from, args.to)?
+    } else {
+        query_json(&args.input, args.from, args.to)?
+    };
+
+    println!("{} entr{} in range", matched.len(), if matched.len() == 1 { "y" } else { "ies" });
+    for stats in &matched {
+        print_current_results(stats);
+    }
+
+    if let Some(output) = &args.output {
+        for stats in &matched {
+            save_results_generic(stats, output)?;
+        }
+        println!("Wrote {} entries to {}", matched.len(), output.display());
+    }
+
+    Ok(())
+}
+
+fn query_json(path: &Path, from: Option<DateTime<Utc>>, to: Option<DateTime<Utc>>) -> Result<Vec<PingStats>> {
+    let file = File::open(path)?;
+    let reader = BufReader::new(file);
+    let entries: Vec<PingStats> = serde_json::from_reader(reader)?;
+    Ok(entries
+        .into_iter()
+        .filter(|e| from.map_or(true, |f| e.timestamp >= f) && to.map_or(true, |t| e.timestamp <= t))
+        .collect())
+}
+
+// Streams the CSV rows in order and stops as soon as a row is past `to`,
+// since both writers always append entries in ascending time order.
+fn query_csv(path: &Path, from: Option<DateTime<Utc>>, to: Option<DateTime<Utc>>) -> Result<Vec<PingStats>> {
+    let file = File::open(path)?;
+    // A file started before `family`/percentiles/jitter were appended has a
+    // shorter header than rows written after the upgrade; `csv::Reader`
+    // otherwise rejects that column-count mismatch outright.
+    let mut reader = csv::ReaderBuilder::new().flexible(true).from_reader(BufReader::new(file));
+    let mut matched = Vec::new();
+
+    for record in reader.records() {
+        let record = record?;
+        let timestamp: DateTime<Utc> = record
+            .get(1)
+            .ok_or_else(|| anyhow!("malformed row: missing timestamp column"))?
+            .parse()?;
+
+        if let Some(to) = to {
+            if timestamp > to {
+                break;
+            }
+        }
+
+        if from.map_or(true, |f| timestamp >= f) {
+            matched.push(csv_record_to_stats(&record, timestamp)?);
+        }
+    }
+
+    Ok(matched)
+}
+
+fn csv_record_to_stats(record: &csv::StringRecord, timestamp: DateTime<Utc>) -> Result<PingStats> {
+    // `family` is the column right after the buckets and absent in rows written
+    // before IPv6 support, so fall back to the same default the JSON side uses.
+    let family = record.get(8 + BUCKET_ORDER.len()).filter(|s| !s.is_empty()).unwrap_or("v4");
+    let mut stats = PingStats::new(record.get(0).unwrap_or_default().to_string(), family.to_string());
+    stats.timestamp = timestamp;
+    stats.sent = record.get(2).unwrap_or("0").parse().unwrap_or(0);
+    stats.received = record.get(3).unwrap_or("0").parse().unwrap_or(0);
+    stats.loss_percent = record.get(4).unwrap_or("0").parse().unwrap_or(0.0);
+    stats.min = record.get(5).filter(|s| !s.is_empty()).and_then(|s| s.parse().ok());
+    stats.max = record.get(6).filter(|s| !s.is_empty()).and_then(|s| s.parse().ok());
+    stats.avg = record.get(7).filter(|s| !s.is_empty()).and_then(|s| s.parse().ok());
+    for (i, bucket) in BUCKET_ORDER.iter().enumerate() {
+        let count = record.get(8 + i).unwrap_or("0").parse().unwrap_or(0);
+        stats.latency_buckets.insert(bucket.to_string(), count);
+    }
+    let percentiles_at = 8 + BUCKET_ORDER.len() + 1; // skip the family column just before these
+    stats.p50 = record.get(percentiles_at).filter(|s| !s.is_empty()).and_then(|s| s.parse().ok());
+    stats.p90 = record.get(percentiles_at + 1).filter(|s| !s.is_empty()).and_then(|s| s.parse().ok());
+    stats.p95 = record.get(percentiles_at + 2).filter(|s| !s.is_empty()).and_then(|s| s.parse().ok());
+    stats.p99 = record.get(percentiles_at + 3).filter(|s| !s.is_empty()).and_then(|s| s.parse().ok());
+    stats.jitter = record.get(percentiles_at + 4).filter(|s| !s.is_empty()).and_then(|s| s.parse().ok());
+    Ok(stats)
+}
+
 fn print_current_results(stats: &PingStats) {
     println!("\n=== Current Session Stats ===");
     println!("Target: {}", stats.target);
@@ -202,6 +413,12 @@ fn print_current_results(stats: &PingStats) {
     } else {
         println!("Latency: No data available.");
     }
+    if let (Some(p50), Some(p90), Some(p95), Some(p99)) = (stats.p50, stats.p90, stats.p95, stats.p99) {
+        println!("Percentiles: p50={:.2}ms, p90={:.2}ms, p95={:.2}ms, p99={:.2}ms", p50, p90, p95, p99);
+    }
+    if let Some(jitter) = stats.jitter {
+        println!("Jitter: {:.2}ms", jitter);
+    }
 }
 
 fn read_line() -> String {
@@ -247,11 +464,20 @@ fn validate_filename(prompt: &str, default: String, format: &str) -> String {
 fn main() -> Result<()> {
     let mut args = Args::parse();
 
-    if args.target.is_none() {
+    if let Some(Command::Query(query_args)) = args.command.take() {
+        return run_query(query_args);
+    }
+
+    if args.daemon && args.targets.is_empty() && args.targets_file.is_none() {
+        return Err(anyhow!("--daemon is headless and cannot prompt; pass a target or --targets-file"));
+    }
+
+    if args.targets.is_empty() && args.targets_file.is_none() {
         println!("For help run pinger_rust.exe -h");
-        args.target = Some({ print!("Enter host to ping (or Enter to exit): "); io::stdout().flush().unwrap(); read_line() });
-        if args.target.as_deref() == Some("") { println!("Exiting."); return Ok(()); }
-        
+        let target = { print!("Enter host to ping (or Enter to exit): "); io::stdout().flush().unwrap(); read_line() };
+        if target.is_empty() { println!("Exiting."); return Ok(()); }
+        args.targets = vec![target];
+
         args.count = validate_int("Number of packets (empty=continuous): ", None, 1);
         args.timeout = validate_float(&format!("Timeout in seconds (default {}): ", args.timeout), args.timeout);
         if let Some(val) = validate_int(&format!("Packet size bytes (default {}): ", args.packet_size), Some(args.packet_size), 0) { args.packet_size = val; }
@@ -284,17 +510,202 @@ fn main() -> Result<()> {
     runtime.block_on(run_ping(args))
 }
 
+// Per-target state shared between that target's ping task, the Ctrl+C
+// handler, and the auto-save task. Each target owns its own stats/times so a
+// single history file can accumulate interleaved per-host sessions.
+pub(crate) struct PingTarget {
+    pub(crate) ip_addr: std::net::IpAddr,
+    tcp_port: u16,
+    pub(crate) stats: Mutex<PingStats>,
+    session_times: Mutex<Vec<f32>>,
+    interval_times: Mutex<Vec<f32>>,
+    interval_sent: Mutex<u64>,
+    // Most recent samples, newest last, capped at `RECENT_SAMPLES`; feeds the `--tui` table.
+    pub(crate) recent: Mutex<VecDeque<f32>>,
+    pusher: Option<Arc<push::Pusher>>,
+}
+
+impl PingTarget {
+    // Resolves a raw `host`, `host:port` (tcp mode), or IP into a ready-to-ping target.
+    async fn resolve(raw: &str, is_tcp: bool, args: &Args, pusher: Option<Arc<push::Pusher>>) -> Result<Self> {
+        let (resolve_host, tcp_port) = if is_tcp {
+            Self::split_host_port(raw, args.port)
+        } else {
+            (raw.to_string(), args.port)
+        };
+
+        // Bracket bare IPv6 literals before handing them to lookup_host, which
+        // parses its argument as a SocketAddr string and would otherwise
+        // misread the literal's own colons as a port separator.
+        let lookup_target = if resolve_host.parse::<std::net::Ipv6Addr>().is_ok() {
+            format!("[{}]:0", resolve_host)
+        } else {
+            format!("{}:0", resolve_host)
+        };
+        let candidates: Vec<std::net::IpAddr> = tokio::net::lookup_host(lookup_target)
+            .await?
+            .map(|addr| addr.ip())
+            .collect();
+
+        // Default to whichever address the resolver returned first; -4/-6 pick
+        // the first match of that family if one is present.
+        let ip_addr = if args.prefer_v4 {
+            candidates.iter().find(|ip| ip.is_ipv4()).or(candidates.first())
+        } else if args.prefer_v6 {
+            candidates.iter().find(|ip| ip.is_ipv6()).or(candidates.first())
+        } else {
+            candidates.first()
+        }
+        .copied()
+        .ok_or_else(|| anyhow!("Could not resolve host: {}", raw))?;
+
+        let family = if ip_addr.is_ipv6() { "v6" } else { "v4" }.to_string();
+
+        Ok(Self {
+            ip_addr,
+            tcp_port,
+            stats: Mutex::new(PingStats::new(ip_addr.to_string(), family)),
+            session_times: Mutex::new(Vec::new()),
+            interval_times: Mutex::new(Vec::new()),
+            interval_sent: Mutex::new(0),
+            recent: Mutex::new(VecDeque::with_capacity(RECENT_SAMPLES)),
+            pusher,
+        })
+    }
+
+    // Splits a tcp-mode target into host and port, handling IPv6 literals: a
+    // bracketed `[addr]:port` is unwrapped, while a bare multi-colon literal
+    // (e.g. `::1`, `2001:db8::1`) is treated as host-only since naively
+    // splitting on the last `:` would chop it into a bogus host/port pair.
+    fn split_host_port(raw: &str, default_port: u16) -> (String, u16) {
+        if let Some(rest) = raw.strip_prefix('[') {
+            if let Some((host, after)) = rest.split_once(']') {
+                return match after.strip_prefix(':').and_then(|p| p.parse::<u16>().ok()) {
+                    Some(port) => (host.to_string(), port),
+                    None => (host.to_string(), default_port),
+                };
+            }
+            return (raw.to_string(), default_port);
+        }
+
+        // More than one colon means a bare IPv6 literal with no port.
+        if raw.matches(':').count() > 1 {
+            return (raw.to_string(), default_port);
+        }
+
+        match raw.rsplit_once(':') {
+            Some((host, port)) if port.parse::<u16>().is_ok() => {
+                (host.to_string(), port.parse::<u16>().unwrap())
+            }
+            _ => (raw.to_string(), default_port),
+        }
+    }
+
+    // Recalculates the cumulative session stats and saves them.
+    fn save_session(&self, path: &Path) -> Result<()> {
+        let mut stats = self.stats.lock().unwrap();
+        let times = self.session_times.lock().unwrap();
+        stats.calculate(&times);
+        if stats.sent > 0 {
+            save_results_generic(&stats, path)?;
+            if let Some(pusher) = &self.pusher {
+                pusher.push(&stats);
+            }
+            print_current_results(&stats);
+        }
+        Ok(())
+    }
+
+    // Snapshots and saves just the current auto-save interval, then resets it.
+    fn save_interval(&self, path: &Path) -> Result<()> {
+        let mut interval_times = self.interval_times.lock().unwrap();
+        let mut interval_sent = self.interval_sent.lock().unwrap();
+
+        let stats_guard = self.stats.lock().unwrap();
+        let mut interval_stat = PingStats::new(stats_guard.target.clone(), stats_guard.family.clone());
+        drop(stats_guard);
+        interval_stat.sent = *interval_sent;
+        interval_stat.calculate(&interval_times);
+        save_results_generic(&interval_stat, path)?;
+        if let Some(pusher) = &self.pusher {
+            pusher.push(&interval_stat);
+        }
+
+        interval_times.clear();
+        *interval_sent = 0;
+        Ok(())
+    }
+
+    // A live snapshot of the current session's stats, recalculated from the
+    // samples seen so far. Used by the `--tui` dashboard; works on a copy, so
+    // unlike `save_session` it leaves the stored stats untouched, and unlike
+    // `save_interval` it does not reset `interval_times`/`interval_sent`.
+    pub(crate) fn snapshot(&self) -> PingStats {
+        let mut stats = self.stats.lock().unwrap().clone();
+        let times = self.session_times.lock().unwrap();
+        stats.calculate(&times);
+        stats
+    }
+
+    pub(crate) fn recent_samples(&self) -> Vec<f32> {
+        self.recent.lock().unwrap().iter().copied().collect()
+    }
+
+    fn record_sample(&self, ms: f32) {
+        self.session_times.lock().unwrap().push(ms);
+        self.interval_times.lock().unwrap().push(ms);
+        let mut recent = self.recent.lock().unwrap();
+        if recent.len() == RECENT_SAMPLES {
+            recent.pop_front();
+        }
+        recent.push_back(ms);
+    }
+}
+
+// Shared by the Ctrl+C and SIGTERM handlers: finalizes and saves every
+// target's session before the process exits.
+fn finalize_and_save(targets: &[Arc<PingTarget>], path: &Path) {
+    for target in targets {
+        if let Err(e) = target.save_session(path) {
+            eprintln!("Failed to save results on exit: {}", e);
+        }
+    }
+    println!("Results saved to {}", path.display());
+}
+
+// Reads the combined target list: positional targets plus any non-empty,
+// non-comment lines from `--targets-file`.
+fn collect_targets(args: &Args) -> Result<Vec<String>> {
+    let mut targets = args.targets.clone();
+    if let Some(path) = &args.targets_file {
+        let contents = std::fs::read_to_string(path)?;
+        for line in contents.lines() {
+            let line = line.trim();
+            if !line.is_empty() && !line.starts_with('#') {
+                targets.push(line.to_string());
+            }
+        }
+    }
+    if targets.is_empty() {
+        return Err(anyhow!("No targets given (positional target or --targets-file)"));
+    }
+    Ok(targets)
+}
+
 async fn run_ping(args: Args) -> Result<()> {
-    let target_host = args.target.clone().unwrap();
-    let ip_addr = match tokio::net::lookup_host(format!("{}:0", target_host)).await?.next() {
-        Some(addr) => match addr.ip() {
-            std::net::IpAddr::V4(ip) => ip,
-            std::net::IpAddr::V6(_) => return Err(anyhow!("IPv6 is not supported yet.")),
-        },
-        None => return Err(anyhow!("Could not resolve host.")),
-    };
+    let is_tcp = args.mode == Mode::Tcp;
+    let raw_targets = collect_targets(&args)?;
 
-    let save_path = match args.directory {
+    // Every target shares one pusher so they all push to the same collector endpoint.
+    let pusher = args.push_url.clone().map(|url| Arc::new(push::Pusher::spawn(url)));
+
+    let mut targets = Vec::with_capacity(raw_targets.len());
+    for raw in &raw_targets {
+        targets.push(Arc::new(PingTarget::resolve(raw, is_tcp, &args, pusher.clone()).await?));
+    }
+    let targets = Arc::new(targets);
+
+    let save_path = match &args.directory {
         Some(dir) => dir.join(&args.output),
         None => PathBuf::from(&args.output),
     };
@@ -308,99 +719,342 @@ async fn run_ping(args: Args) -> Result<()> {
         save_path
     };
 
-    // Shared state for the current session.
-    let session_stats = Arc::new(Mutex::new(PingStats::new(ip_addr.to_string())));
-    let session_times = Arc::new(Mutex::new(Vec::<f32>::new()));
-
-    println!("Pinging {}...", ip_addr);
+    for target in targets.iter() {
+        println!("Pinging {}...", target.ip_addr);
+    }
 
-    // Set up Ctrl+C handler
-    let stats_clone_ctrlc = Arc::clone(&session_stats);
-    let times_clone_ctrlc = Arc::clone(&session_times);
-    let save_path_clone_ctrlc = final_save_path.clone();
+    // Set up Ctrl+C handler: iterate every target, finalize and save its session.
+    let targets_ctrlc = Arc::clone(&targets);
+    let save_path_ctrlc = final_save_path.clone();
+    let daemon = args.daemon;
     ctrlc::set_handler(move || {
         println!("\nInterrupted by user. Saving results...");
-        let mut stats = stats_clone_ctrlc.lock().unwrap();
-        let times = times_clone_ctrlc.lock().unwrap();
-        stats.calculate(&times); // Final calculation before saving
-        if let Err(e) = save_results_generic(&stats, &save_path_clone_ctrlc) {
-            eprintln!("Failed to save results on exit: {}", e);
-        } else {
-            println!("Results saved to {}", save_path_clone_ctrlc.display());
+        finalize_and_save(&targets_ctrlc, &save_path_ctrlc);
+        if daemon {
+            let _ = sd_notify::notify(false, &[sd_notify::NotifyState::Stopping]);
         }
-        print_current_results(&stats);
         std::process::exit(0);
     })?;
 
-    let client = Client::new(&Config::default())?;
-    let ident = PingIdentifier(rand::random());
-    let mut pinger = client.pinger(std::net::IpAddr::V4(ip_addr), ident).await;
-    pinger.timeout(Duration::from_secs_f64(args.timeout));
+    // systemd service mode: handle SIGTERM through the same save path as Ctrl+C,
+    // then report STOPPING=1 before exiting.
+    #[cfg(unix)]
+    if args.daemon {
+        let targets_term = Arc::clone(&targets);
+        let save_path_term = final_save_path.clone();
+        tokio::spawn(async move {
+            let mut term = match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+                Ok(term) => term,
+                Err(e) => { eprintln!("Failed to register SIGTERM handler: {}", e); return; }
+            };
+            term.recv().await;
+            println!("\nReceived SIGTERM. Saving results...");
+            finalize_and_save(&targets_term, &save_path_term);
+            let _ = sd_notify::notify(false, &[sd_notify::NotifyState::Stopping]);
+            std::process::exit(0);
+        });
+    }
 
-    let mut last_save_time = tokio::time::Instant::now();
-    let save_interval_duration = args.save_interval.map(Duration::from_secs);
+    // Only ICMP mode needs a raw-socket client, and a client's socket family
+    // is fixed at creation; targets can resolve to a mix of v4 and v6
+    // addresses, so build one client per family actually in use rather than
+    // a single client keyed off the `-4/-6` flag.
+    let icmp_clients: HashMap<bool, Arc<Client>> = if is_tcp {
+        HashMap::new()
+    } else {
+        let mut clients = HashMap::new();
+        for target in targets.iter() {
+            let is_v6 = target.ip_addr.is_ipv6();
+            if let std::collections::hash_map::Entry::Vacant(entry) = clients.entry(is_v6) {
+                let icmp_kind = if is_v6 { ICMP::V6 } else { ICMP::V4 };
+                let config = Config::builder().kind(icmp_kind).build();
+                entry.insert(Arc::new(Client::new(&config)?));
+            }
+        }
+        clients
+    };
 
-    // State for each auto-save interval
-    let mut interval_times = Vec::<f32>::new();
-    let mut interval_sent: u64 = 0;
+    if args.daemon {
+        sd_notify::notify(false, &[sd_notify::NotifyState::Ready])?;
+    }
+
+    // Auto-save task: periodically iterates every target and writes its interval snapshot.
+    if let Some(interval_secs) = args.save_interval {
+        let targets_autosave = Arc::clone(&targets);
+        let save_path_autosave = final_save_path.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(Duration::from_secs(interval_secs));
+            ticker.tick().await; // first tick fires immediately
+            loop {
+                ticker.tick().await;
+                for target in targets_autosave.iter() {
+                    if let Err(e) = target.save_interval(&save_path_autosave) {
+                        eprintln!("Failed to auto-save results: {}", e);
+                    }
+                }
+                println!("\n--- Auto-saved interval results to {} ---\n", save_path_autosave.display());
+            }
+        });
+    }
+
+    // The dashboard takes over the screen, so it's incompatible with the
+    // per-packet `println!`s; fall back to line-by-line output when stdout
+    // isn't a real terminal (e.g. redirected to a file or another tool).
+    let use_tui = args.tui && io::stdout().is_terminal();
+    if args.tui && !use_tui {
+        println!("--tui requested but stdout is not a terminal; falling back to line-by-line output.");
+    }
+
+    // Two flags, not one: `tui_done` tells the dashboard the ping loops
+    // finished on their own (finite `--count`); `stop` tells the ping loops
+    // the dashboard was quit by the user. Without the latter, quitting the
+    // TUI never reached the ping tasks and a continuous run just hung.
+    let tui_done = Arc::new(std::sync::atomic::AtomicBool::new(false));
+    let stop = Arc::new(std::sync::atomic::AtomicBool::new(false));
+    let tui_handle = if use_tui {
+        let targets_tui = Arc::clone(&targets);
+        let tui_done = Arc::clone(&tui_done);
+        let stop_tui = Arc::clone(&stop);
+        Some(tokio::task::spawn_blocking(move || tui::run(targets_tui, tui_done, stop_tui)))
+    } else {
+        None
+    };
 
-    let mut seq: u16 = 0;
     let packets_to_send = args.count.unwrap_or(u64::MAX);
+    let mut handles = Vec::with_capacity(targets.len());
+    for target in targets.iter() {
+        let target = Arc::clone(target);
+        let icmp_client = icmp_clients.get(&target.ip_addr.is_ipv6()).cloned();
+        let timeout = args.timeout;
+        let packet_size = args.packet_size;
+        let daemon = args.daemon;
+        let stop = Arc::clone(&stop);
+        handles.push(tokio::spawn(async move {
+            ping_target_loop(target, is_tcp, icmp_client, timeout, packet_size, packets_to_send, use_tui, daemon, stop).await
+        }));
+    }
 
-    for i in 0..packets_to_send {
-        // Increment counters for both the overall session and the current interval
-        session_stats.lock().unwrap().sent += 1;
-        interval_sent += 1;
-
-        match pinger.ping(PingSequence(seq), &vec![0; args.packet_size]).await {
-            Ok((_, dur)) => {
-                let ms = dur.as_secs_f32() * 1000.0;
-                println!("Reply from {}: icmp_seq={} time={:.2}ms", ip_addr, i, ms);
-                // Record time for both session and interval
-                session_times.lock().unwrap().push(ms);
-                interval_times.push(ms);
-            }
-            Err(e) => { println!("Request timed out or error: {}", e); }
+    // Wait for whichever finishes first: every ping loop completing on its
+    // own (finite `--count`), or the user quitting the dashboard. Either way
+    // the other side is then told to wind down instead of one of them
+    // blocking the process forever. The ping loops run under their own task
+    // so it can still be awaited after losing the select below.
+    let mut ping_loops = tokio::spawn(async move {
+        for handle in handles {
+            handle.await??;
         }
-        seq = seq.wrapping_add(1);
+        Ok::<(), anyhow::Error>(())
+    });
 
-        // Auto-save logic for the interval
-        if let Some(interval) = save_interval_duration {
-            if last_save_time.elapsed() >= interval {
-                // Create a new stats object specifically for the interval
-                let mut interval_stat = PingStats::new(ip_addr.to_string());
-                interval_stat.sent = interval_sent;
-                interval_stat.calculate(&interval_times);
-
-                if let Err(e) = save_results_generic(&interval_stat, &final_save_path) {
-                    eprintln!("Failed to auto-save results: {}", e);
-                } else {
-                    println!("\n--- Auto-saved interval results to {} ---\n", final_save_path.display());
-                }
-                
-                // Reset interval-specific state
-                interval_times.clear();
-                interval_sent = 0;
-                last_save_time = tokio::time::Instant::now();
+    if let Some(tui_handle) = tui_handle {
+        tokio::select! {
+            res = &mut ping_loops => {
+                res??;
+                tui_done.store(true, std::sync::atomic::Ordering::SeqCst);
+                tui_handle.await??;
+            }
+            res = tui_handle => {
+                res??;
+                stop.store(true, std::sync::atomic::Ordering::SeqCst);
+                (&mut ping_loops).await??;
             }
         }
+    } else {
+        ping_loops.await??;
+    }
 
-        tokio::time::sleep(Duration::from_secs(1)).await;
+    // This is reached only once every target's loop has actually stopped
+    // (finite `--count` ran out, or the dashboard quit and `stop` drained them).
+    for target in targets.iter() {
+        target.save_session(&final_save_path)?;
     }
+    println!("Final results saved to {}", final_save_path.display());
 
-    // This part is reached only if the loop finishes (i.e., `count` was specified).
-    let mut stats_guard = session_stats.lock().unwrap();
-    let times_guard = session_times.lock().unwrap();
-    stats_guard.calculate(&times_guard);
+    Ok(())
+}
+
+// Runs the send loop for a single target, recording each reply/loss into
+// that target's session and interval state.
+async fn ping_target_loop(
+    target: Arc<PingTarget>,
+    is_tcp: bool,
+    icmp_client: Option<Arc<Client>>,
+    timeout: f64,
+    packet_size: usize,
+    packets_to_send: u64,
+    quiet: bool,
+    daemon: bool,
+    stop: Arc<std::sync::atomic::AtomicBool>,
+) -> Result<()> {
+    let ip_addr = target.ip_addr;
+    let tcp_port = target.tcp_port;
 
-    if stats_guard.sent > 0 {
-        if let Err(e) = save_results_generic(&stats_guard, &final_save_path) {
-            eprintln!("Failed to save final results: {}", e);
+    let mut icmp_pinger = if is_tcp {
+        None
+    } else {
+        let client = icmp_client.expect("icmp client must be set up in icmp mode");
+        let ident = PingIdentifier(rand::random());
+        let mut pinger = client.pinger(ip_addr, ident).await;
+        pinger.timeout(Duration::from_secs_f64(timeout));
+        Some(pinger)
+    };
+
+    let mut seq: u16 = 0;
+    for i in 0..packets_to_send {
+        // Checked once per packet rather than via cancellation so a send
+        // already in flight is allowed to finish and record its sample.
+        if stop.load(std::sync::atomic::Ordering::SeqCst) {
+            break;
+        }
+
+        target.stats.lock().unwrap().sent += 1;
+        *target.interval_sent.lock().unwrap() += 1;
+
+        if is_tcp {
+            let start = tokio::time::Instant::now();
+            let connect = tokio::time::timeout(
+                Duration::from_secs_f64(timeout),
+                tokio::net::TcpStream::connect((ip_addr, tcp_port)),
+            )
+            .await;
+            match connect {
+                Ok(Ok(_stream)) => {
+                    let ms = start.elapsed().as_secs_f32() * 1000.0;
+                    if !quiet {
+                        println!("Reply from {}:{}: tcp_seq={} time={:.2}ms", ip_addr, tcp_port, i, ms);
+                    }
+                    target.record_sample(ms);
+                }
+                Ok(Err(e)) => { if !quiet { println!("Connection failed ({}): {}", ip_addr, e); } }
+                Err(_) => { if !quiet { println!("Connection timed out ({})", ip_addr); } }
+            }
         } else {
-            println!("Final results saved to {}", final_save_path.display());
+            let pinger = icmp_pinger.as_mut().unwrap();
+            match pinger.ping(PingSequence(seq), &vec![0; packet_size]).await {
+                Ok((_, dur)) => {
+                    let ms = dur.as_secs_f32() * 1000.0;
+                    if !quiet {
+                        println!("Reply from {}: icmp_seq={} time={:.2}ms", ip_addr, i, ms);
+                    }
+                    target.record_sample(ms);
+                }
+                Err(e) => { if !quiet { println!("Request timed out or error ({}): {}", ip_addr, e); } }
+            }
         }
-        print_current_results(&stats_guard);
+        seq = seq.wrapping_add(1);
+
+        // A resolver/socket wedged badly enough to never complete a send would
+        // stop these heartbeats, so systemd's watchdog restarts the unit.
+        if daemon {
+            let _ = sd_notify::notify(false, &[sd_notify::NotifyState::Watchdog]);
+        }
+
+        tokio::time::sleep(Duration::from_secs(1)).await;
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_host_port_plain_host_and_port() {
+        assert_eq!(PingTarget::split_host_port("example.com:8080", 443), ("example.com".to_string(), 8080));
+    }
+
+    #[test]
+    fn split_host_port_falls_back_to_default_port() {
+        assert_eq!(PingTarget::split_host_port("example.com", 443), ("example.com".to_string(), 443));
+    }
+
+    #[test]
+    fn split_host_port_bracketed_ipv6_with_port() {
+        assert_eq!(PingTarget::split_host_port("[::1]:443", 80), ("::1".to_string(), 443));
+    }
+
+    #[test]
+    fn split_host_port_bracketed_ipv6_without_port_uses_default() {
+        assert_eq!(PingTarget::split_host_port("[2001:db8::1]", 80), ("2001:db8::1".to_string(), 80));
+    }
+
+    #[test]
+    fn split_host_port_bare_ipv6_literal_is_host_only() {
+        // A naive rsplit_once(':') would misread this as host=":", port=1.
+        assert_eq!(PingTarget::split_host_port("::1", 443), ("::1".to_string(), 443));
+        assert_eq!(PingTarget::split_host_port("2001:db8::1", 443), ("2001:db8::1".to_string(), 443));
+    }
+
+    #[test]
+    fn calculate_nearest_rank_percentiles() {
+        let mut stats = PingStats::new("example.com".to_string(), "v4".to_string());
+        stats.sent = 10;
+        // Nearest-rank on 10 sorted samples: p50 -> rank 4 (value 50), p90 -> rank 8 (value 90).
+        let times: Vec<f32> = (1..=10).map(|n| n as f32 * 10.0).collect();
+        stats.calculate(&times);
+        assert_eq!(stats.p50, Some(50.0));
+        assert_eq!(stats.p90, Some(90.0));
+        assert_eq!(stats.p95, Some(100.0));
+        assert_eq!(stats.p99, Some(100.0));
+    }
+
+    #[test]
+    fn calculate_jitter_is_mean_absolute_consecutive_difference() {
+        let mut stats = PingStats::new("example.com".to_string(), "v4".to_string());
+        stats.sent = 3;
+        stats.calculate(&[10.0, 15.0, 5.0]);
+        // |15-10| + |5-15| = 5 + 10 = 15, over 2 gaps -> 7.5
+        assert_eq!(stats.jitter, Some(7.5));
+    }
+
+    #[test]
+    fn calculate_percentiles_and_jitter_are_none_with_no_samples() {
+        let mut stats = PingStats::new("example.com".to_string(), "v4".to_string());
+        stats.sent = 1;
+        stats.calculate(&[]);
+        assert_eq!(stats.p50, None);
+        assert_eq!(stats.jitter, None);
+    }
+
+    #[test]
+    fn query_csv_round_trips_a_saved_session_and_filters_by_time() {
+        let path = std::env::temp_dir().join(format!("rusty_pinger_test_{}.csv", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let mut stats = PingStats::new("example.com".to_string(), "v6".to_string());
+        stats.sent = 4;
+        stats.calculate(&[10.0, 20.0, 30.0]);
+        save_results_csv(&stats, &path).unwrap();
+
+        let matched = query_csv(&path, None, None).unwrap();
+        assert_eq!(matched.len(), 1);
+        assert_eq!(matched[0].target, "example.com");
+        assert_eq!(matched[0].family, "v6");
+        assert_eq!(matched[0].sent, 4);
+        assert_eq!(matched[0].p50, stats.p50);
+        assert_eq!(matched[0].jitter, stats.jitter);
+
+        let after_the_row = stats.timestamp + chrono::Duration::seconds(1);
+        let none_matched = query_csv(&path, Some(after_the_row), None).unwrap();
+        assert!(none_matched.is_empty());
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn csv_record_to_stats_defaults_family_for_a_pre_ipv6_header() {
+        // Rows written before `family`/percentiles/jitter existed only had the
+        // original 20 columns; csv_record_to_stats should still parse them.
+        let record = csv::StringRecord::from(vec![
+            "example.com", "2024-01-01T00:00:00Z", "4", "3", "25.00",
+            "10.00", "30.00", "20.00", "1", "1", "1", "0", "0", "0", "0",
+            "0", "0", "0", "0", "0",
+        ]);
+        let timestamp = Utc::now();
+        let stats = csv_record_to_stats(&record, timestamp).unwrap();
+        assert_eq!(stats.family, "v4");
+        assert_eq!(stats.sent, 4);
+        assert_eq!(stats.p50, None);
+    }
+}
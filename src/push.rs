@@ -0,0 +1,73 @@
+// Streams completed interval/session `PingStats` to a remote collector, in
+// addition to the local file saving `save_results_generic` already does.
+// Supports a one-shot HTTP POST per payload and a persistent WebSocket
+// connection that reconnects after a fixed delay if it drops.
+
+use crate::PingStats;
+use futures_util::{SinkExt, StreamExt};
+use tokio::sync::mpsc::{self, UnboundedSender};
+use tokio_tungstenite::tungstenite::Message;
+
+const RECONNECT_DELAY: std::time::Duration = std::time::Duration::from_secs(5);
+
+pub(crate) struct Pusher {
+    sender: UnboundedSender<PingStats>,
+}
+
+impl Pusher {
+    pub(crate) fn spawn(url: String) -> Self {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        if url.starts_with("ws://") || url.starts_with("wss://") {
+            tokio::spawn(run_websocket(url, receiver));
+        } else {
+            tokio::spawn(run_http(url, receiver));
+        }
+        Self { sender }
+    }
+
+    // Best-effort; the channel is unbounded, and a dead receiver task just drops the payload.
+    pub(crate) fn push(&self, stats: &PingStats) {
+        let _ = self.sender.send(stats.clone());
+    }
+}
+
+async fn run_http(url: String, mut receiver: mpsc::UnboundedReceiver<PingStats>) {
+    let client = reqwest::Client::new();
+    while let Some(stats) = receiver.recv().await {
+        if let Err(e) = client.post(&url).json(&stats).send().await {
+            eprintln!("push-url: failed to POST stats to {}: {}", url, e);
+        }
+    }
+}
+
+async fn run_websocket(url: String, mut receiver: mpsc::UnboundedReceiver<PingStats>) {
+    loop {
+        let (ws_stream, _) = match tokio_tungstenite::connect_async(&url).await {
+            Ok(conn) => conn,
+            Err(e) => {
+                eprintln!("push-url: websocket connect to {} failed: {}, retrying in {:?}", url, e, RECONNECT_DELAY);
+                tokio::time::sleep(RECONNECT_DELAY).await;
+                continue;
+            }
+        };
+        let (mut write, _read) = ws_stream.split();
+
+        loop {
+            let Some(stats) = receiver.recv().await else {
+                return; // sender dropped, nothing left to push
+            };
+            let payload = match serde_json::to_string(&stats) {
+                Ok(p) => p,
+                Err(e) => {
+                    eprintln!("push-url: failed to serialize stats: {}", e);
+                    continue;
+                }
+            };
+            if write.send(Message::Text(payload)).await.is_err() {
+                eprintln!("push-url: websocket connection to {} dropped, reconnecting in {:?}", url, RECONNECT_DELAY);
+                tokio::time::sleep(RECONNECT_DELAY).await;
+                break;
+            }
+        }
+    }
+}
@@ -0,0 +1,119 @@
+// Live terminal dashboard for `--tui`: one stacked panel per target, each
+// showing a header line, a table of the most recent samples, and a bar
+// rendering of the latency bucket distribution. Driven off the same
+// `PingTarget` state the ping loop already maintains.
+
+use anyhow::Result;
+use crossterm::event::{self, Event, KeyCode};
+use crossterm::terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{execute, ExecutableCommand};
+use ratatui::backend::CrosstermBackend;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{BarChart, Block, Borders, Paragraph, Row, Table};
+use ratatui::Terminal;
+use std::io::stdout;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::{PingTarget, BUCKET_ORDER};
+
+const TICK: Duration = Duration::from_millis(500);
+
+// Runs the dashboard until `done` is set (the ping loops finished on their
+// own) or the user presses 'q'/Esc, in which case `stop` is set so the ping
+// loops wind down too; either way, the caller is the one that joins the ping
+// tasks and does the final save once everything has actually stopped.
+pub(crate) fn run(targets: Arc<Vec<Arc<PingTarget>>>, done: Arc<AtomicBool>, stop: Arc<AtomicBool>) -> Result<()> {
+    enable_raw_mode()?;
+    execute!(stdout(), EnterAlternateScreen)?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(stdout()))?;
+
+    let result = event_loop(&mut terminal, &targets, &done);
+
+    disable_raw_mode()?;
+    stdout().execute(LeaveAlternateScreen)?;
+
+    if matches!(result, Ok(true)) {
+        stop.store(true, Ordering::SeqCst);
+    }
+
+    result.map(|_| ())
+}
+
+// Returns Ok(true) if the user quit the dashboard, Ok(false) if `done` fired first.
+fn event_loop(
+    terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>,
+    targets: &Arc<Vec<Arc<PingTarget>>>,
+    done: &Arc<AtomicBool>,
+) -> Result<bool> {
+    loop {
+        terminal.draw(|f| draw(f, targets))?;
+
+        if event::poll(TICK)? {
+            if let Event::Key(key) = event::read()? {
+                if matches!(key.code, KeyCode::Char('q') | KeyCode::Esc) {
+                    return Ok(true);
+                }
+            }
+        }
+
+        if done.load(Ordering::SeqCst) {
+            return Ok(false);
+        }
+    }
+}
+
+fn draw(f: &mut ratatui::Frame, targets: &Arc<Vec<Arc<PingTarget>>>) {
+    let panel_height = 10;
+    let constraints: Vec<Constraint> = targets.iter().map(|_| Constraint::Length(panel_height)).collect();
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(constraints)
+        .split(f.size());
+
+    for (target, chunk) in targets.iter().zip(chunks.iter()) {
+        draw_target_panel(f, target, *chunk);
+    }
+}
+
+fn draw_target_panel(f: &mut ratatui::Frame, target: &Arc<PingTarget>, area: Rect) {
+    let stats = target.snapshot();
+    let sections = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(3), Constraint::Length(4)])
+        .split(area);
+
+    let header = Paragraph::new(Line::from(vec![
+        Span::raw(format!(
+            "{} ({})  sent={} received={} loss={:.1}%",
+            stats.target, stats.family, stats.sent, stats.received, stats.loss_percent
+        )),
+    ]))
+    .block(Block::default().borders(Borders::ALL).title("Target"));
+    f.render_widget(header, sections[0]);
+
+    let rows: Vec<Row> = target
+        .recent_samples()
+        .iter()
+        .rev()
+        .map(|ms| Row::new(vec![format!("{:.2}ms", ms)]))
+        .collect();
+    let table = Table::new(rows, [Constraint::Percentage(100)])
+        .header(Row::new(vec!["Recent samples"]))
+        .block(Block::default().borders(Borders::ALL).title("Latency"));
+    f.render_widget(table, sections[1]);
+
+    let bucket_data: Vec<(&str, u64)> = BUCKET_ORDER
+        .iter()
+        .map(|bucket| (*bucket, *stats.latency_buckets.get(*bucket).unwrap_or(&0)))
+        .collect();
+    let bars = BarChart::default()
+        .block(Block::default().borders(Borders::ALL).title("Distribution"))
+        .data(&bucket_data)
+        .bar_width(6)
+        .bar_style(Style::default().fg(Color::Cyan));
+    f.render_widget(bars, sections[2]);
+}